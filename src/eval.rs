@@ -0,0 +1,72 @@
+use svm;
+use svm::slist::{List, Stack};
+use svm::slist::List::Nil;
+use svm::cell::SVMCell;
+
+use printer;
+
+/// Evaluates a program and returns the single value it leaves on the stack.
+///
+/// A program which finishes with an empty stack, or with more than one
+/// value left on it, is treated as an error.
+pub fn eval_value(program: List<SVMCell>, debug: bool) -> Result<SVMCell, String> {
+    svm::eval_program(program, debug)
+        .and_then(|stack| match stack.pop() {
+            Some((value, Nil))  => Ok(value),
+            Some((_, rest))     => Err(format!(
+                "[error]: program left {} unexpected value(s) on the stack: {:?}",
+                rest.length(), rest)),
+            None                => Err(String::from(
+                "[error]: program finished with an empty stack"))
+        })
+}
+
+/// Formats the result of evaluating a program for display.
+///
+/// Results are printed as Scheme data unless `raw` is set, in which case
+/// their `Debug` form is printed instead.
+pub fn format_result(value: &SVMCell, raw: bool) -> String {
+    if raw {
+        format!("===> {:?}", value)
+    } else {
+        format!("===> {}", printer::write(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::eval_value;
+
+    use svm::slist::List::{Cons,Nil};
+    use svm::cell::SVMCell::*;
+    use svm::cell::Atom::*;
+    use svm::cell::Inst::*;
+
+    #[test]
+    fn test_eval_value_single() {
+        assert_eq!(
+            eval_value(list!(InstCell(LDC), AtomCell(SInt(1))), false),
+            Ok(AtomCell(SInt(1)))
+        );
+    }
+
+    #[test]
+    fn test_eval_value_leftover_values() {
+        assert_eq!(
+            eval_value(list!(
+                InstCell(LDC), AtomCell(SInt(1)),
+                InstCell(LDC), AtomCell(SInt(2))
+            ), false),
+            Err(String::from(
+                "[error]: program left 1 unexpected value(s) on the stack: (1 . nil)"))
+        );
+    }
+
+    #[test]
+    fn test_eval_value_empty_stack() {
+        assert_eq!(
+            eval_value(list!(InstCell(STOP)), false),
+            Err(String::from("[error]: program finished with an empty stack"))
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use svm::slist::List;
+use svm::cell::SVMCell;
+
+use scheme;
+
+/// A language front-end targeting the SVM.
+///
+/// Front-ends are responsible for turning source code in some language
+/// into an SVM control list which may then be evaluated. The command-line
+/// application selects a front-end based on the extension of the input file.
+pub trait Frontend {
+    /// A human-readable name for the language, used in log messages.
+    fn name(&self) -> &'static str;
+
+    /// The file extensions (including the leading `.`) handled by this
+    /// front-end.
+    fn extensions(&self) -> &'static [&'static str];
+
+    /// Compile a program into a list of SVM cells (a control stack).
+    fn compile(&self, source: &str) -> Result<List<SVMCell>, String>;
+}
+
+static SCHEME_EXTENSIONS: &'static [&'static str] = &[".scm"];
+
+/// Front-end for Seax Scheme.
+pub struct Scheme;
+
+impl Frontend for Scheme {
+    fn name(&self) -> &'static str { "Scheme" }
+
+    fn extensions(&self) -> &'static [&'static str] { SCHEME_EXTENSIONS }
+
+    fn compile(&self, source: &str) -> Result<List<SVMCell>, String> {
+        scheme::compile(source)
+    }
+}
+
+/// The set of front-ends known to the command-line application.
+pub struct Registry {
+    frontends: Vec<Box<Frontend>>
+}
+
+impl Registry {
+
+    /// Creates a new registry with no front-ends registered.
+    pub fn new() -> Registry {
+        Registry { frontends: Vec::new() }
+    }
+
+    /// Creates a new registry containing all of the front-ends that ship
+    /// with Seax.
+    pub fn with_defaults() -> Registry {
+        let mut registry = Registry::new();
+        registry.register(Box::new(Scheme));
+        registry
+    }
+
+    /// Registers a front-end.
+    ///
+    /// If a previously registered front-end claims one of the same
+    /// extensions, the front-end registered first takes precedence.
+    pub fn register(&mut self, frontend: Box<Frontend>) {
+        self.frontends.push(frontend);
+    }
+
+    /// Returns the front-end responsible for the given file extension
+    /// (including the leading `.`), if any.
+    pub fn by_extension(&self, ext: &str) -> Option<&Frontend> {
+        self.frontends.iter()
+            .find(|f| f.extensions().iter().any(|e| *e == ext))
+            .map(|f| &**f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Frontend, Registry};
+
+    use svm::slist::List;
+    use svm::slist::List::Nil;
+    use svm::cell::SVMCell;
+
+    static STUB_EXTENSIONS: &'static [&'static str] = &[".stub"];
+
+    /// Front-end which compiles everything to an empty program.
+    struct Stub(&'static str);
+
+    impl Frontend for Stub {
+        fn name(&self) -> &'static str { self.0 }
+
+        fn extensions(&self) -> &'static [&'static str] { STUB_EXTENSIONS }
+
+        fn compile(&self, _: &str) -> Result<List<SVMCell>, String> {
+            Ok(Nil)
+        }
+    }
+
+    #[test]
+    fn test_first_registered_wins() {
+        let mut registry = Registry::new();
+        registry.register(Box::new(Stub("first")));
+        registry.register(Box::new(Stub("second")));
+        assert_eq!(registry.by_extension(".stub").map(|f| f.name()),
+            Some("first"));
+    }
+
+    #[test]
+    fn test_unknown_extension() {
+        let mut registry = Registry::with_defaults();
+        registry.register(Box::new(Stub("stub")));
+        assert!(registry.by_extension(".txt").is_none());
+        assert!(registry.by_extension("").is_none());
+        assert_eq!(registry.by_extension(".scm").map(|f| f.name()),
+            Some("Scheme"));
+    }
+}
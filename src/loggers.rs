@@ -1,30 +1,132 @@
-use log::{LogRecord, LogLevel, LogMetadata, Log};
+use log::{LogRecord, LogLevel, LogLevelFilter, LogMetadata, Log};
 
-#[allow(dead_code)]
-pub struct DefaultLogger;
-#[allow(dead_code)]
-pub struct DebugLogger;
+use std::io;
+use std::io::Write;
 
-impl Log for DefaultLogger {
-    fn enabled(&self, metadata: &LogMetadata) -> bool {
-        metadata.level() <= LogLevel::Info
+/// Log target for the Seax virtual machine.
+pub const TARGET_SVM: &'static str = "seax_svm";
+/// Log target for the Seax Scheme compiler (parser and code generation).
+pub const TARGET_SCHEME: &'static str = "seax_scheme";
+
+/// Logger which filters records by their target.
+///
+/// Each record is checked against the most specific target prefix that
+/// has been given its own level (so `seax_scheme::parser` overrides
+/// `seax_scheme`), falling back to the default level. Targets are the
+/// module paths of the crates that emit them, so `seax_svm` covers
+/// everything logged by the VM.
+pub struct TargetLogger {
+    default: LogLevelFilter,
+    targets: Vec<(&'static str, LogLevelFilter)>
+}
+
+impl TargetLogger {
+
+    /// Creates a new logger which logs all targets at `default`.
+    pub fn new(default: LogLevelFilter) -> TargetLogger {
+        TargetLogger { default: default, targets: Vec::new() }
     }
 
-    fn log(&self, record: &LogRecord) {
-        if self.enabled(record.metadata()) {
-            println!("{} - {}", record.level(), record.args());
-        }
+    /// Sets the level for all records whose target is `target` or
+    /// one of its submodules.
+    pub fn set_level(&mut self, target: &'static str, level: LogLevelFilter) {
+        self.targets.retain(|&(t, _)| t != target);
+        self.targets.push((target, level));
+    }
+
+    /// The most verbose level enabled for any target.
+    pub fn max_level(&self) -> LogLevelFilter {
+        self.targets.iter()
+            .map(|&(_, level)| level)
+            .fold(self.default, |a, b| if b > a { b } else { a })
+    }
+
+    fn level_for(&self, target: &str) -> LogLevelFilter {
+        self.targets.iter()
+            .filter(|&&(t, _)|
+                target == t ||
+                (target.starts_with(t) && target[t.len()..].starts_with("::")))
+            .fold(None, |best: Option<&(&str, LogLevelFilter)>, it|
+                match best {
+                    Some(b) if b.0.len() >= it.0.len() => Some(b),
+                    _ => Some(it)
+                })
+            .map_or(self.default, |&(_, level)| level)
     }
 }
 
-impl Log for DebugLogger {
+impl Log for TargetLogger {
     fn enabled(&self, metadata: &LogMetadata) -> bool {
-        metadata.level() <= LogLevel::Debug
+        metadata.level() <= self.level_for(metadata.target())
     }
 
     fn log(&self, record: &LogRecord) {
         if self.enabled(record.metadata()) {
-            println!("{} - {}", record.level(), record.args());
+            // log records go to stderr, so they don't get mixed up with
+            // program output on stdout
+            let _ = if record.level() <= LogLevel::Info {
+                writeln!(io::stderr(), "{} - {}", record.level(), record.args())
+            } else {
+                writeln!(io::stderr(), "{} [{}] - {}",
+                    record.level(), record.target(), record.args())
+            };
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::TargetLogger;
+    use log::LogLevelFilter::*;
+
+    #[test]
+    fn test_level_for_default() {
+        let logger = TargetLogger::new(Info);
+        assert_eq!(logger.level_for("seax"), Info);
+        assert_eq!(logger.level_for("seax_svm"), Info);
+    }
+
+    #[test]
+    fn test_level_for_target_and_submodules() {
+        let mut logger = TargetLogger::new(Info);
+        logger.set_level("seax_svm", Trace);
+        assert_eq!(logger.level_for("seax_svm"), Trace);
+        assert_eq!(logger.level_for("seax_svm::slist"), Trace);
+        assert_eq!(logger.level_for("seax_scheme"), Info);
+    }
+
+    #[test]
+    fn test_level_for_module_boundary() {
+        let mut logger = TargetLogger::new(Info);
+        logger.set_level("seax_svm", Trace);
+        assert_eq!(logger.level_for("seax_svmx"), Info);
+        assert_eq!(logger.level_for("seax_sv"), Info);
+    }
+
+    #[test]
+    fn test_level_for_longest_prefix() {
+        let mut logger = TargetLogger::new(Info);
+        logger.set_level("seax_scheme::parser", Warn);
+        logger.set_level("seax_scheme", Trace);
+        assert_eq!(logger.level_for("seax_scheme::parser"), Warn);
+        assert_eq!(logger.level_for("seax_scheme::parser::tests"), Warn);
+        assert_eq!(logger.level_for("seax_scheme::ast"), Trace);
+    }
+
+    #[test]
+    fn test_set_level_replaces() {
+        let mut logger = TargetLogger::new(Info);
+        logger.set_level("seax_svm", Trace);
+        logger.set_level("seax_svm", Error);
+        assert_eq!(logger.level_for("seax_svm"), Error);
+        assert_eq!(logger.max_level(), Info);
+    }
+
+    #[test]
+    fn test_max_level() {
+        let mut logger = TargetLogger::new(Info);
+        assert_eq!(logger.max_level(), Info);
+        logger.set_level("seax_svm", Trace);
+        assert_eq!(logger.max_level(), Trace);
+    }
+}
@@ -3,6 +3,8 @@
 #![feature(box_patterns,box_syntax)]
 #![feature(scheme)]
 #![feature(compile)]
+#![feature(parser)]
+#![feature(ast)]
 #![feature(convert)]
 
 //! Seax
@@ -15,6 +17,7 @@ extern crate rustc_serialize;
 extern crate docopt;
 extern crate regex;
 
+#[macro_use]
 extern crate seax_svm as svm;
 extern crate seax_scheme as scheme;
 
@@ -24,23 +27,33 @@ extern crate log;
 use docopt::Docopt;
 use regex::Regex;
 
+use svm::slist::List;
+use svm::cell::SVMCell;
+
 use std::io;
-use std::io::{Write, Read, BufRead,BufReader};
+use std::io::Read;
 use std::error::Error;
 use std::fs::File;
 use std::path::PathBuf;
-use std::convert::AsRef;
+use std::process;
 
 #[allow(dead_code)]
 static USAGE: &'static str = "
 Usage:
-    seax repl [-vd]
-    seax [-vd] <file>
-    seax compile [-vd] file
+    seax repl [-vd] [--raw] [--trace-vm] [--trace-compile]
+    seax [-vd] [--raw] [--trace-vm] [--trace-compile] <file>
+    seax compile [-v] [--trace-compile] <file>
+    seax check [-v] [--trace-compile] <file>
+
+Use `-` as <file> to read a program from standard input.
 
 Options:
-    -v, --verbose   Enable verbose mode
-    -d, --debug     Enable debug mode
+    -v, --verbose                   Enable verbose mode
+    -d, --debug                     Enable debug mode
+    --raw                           Print results in the VM's internal form
+                                    rather than as Scheme data
+    --trace-vm                      Trace every step of the virtual machine
+    --trace-compile                 Trace the parser and compiler
 ";
 
 #[derive(RustcDecodable)]
@@ -48,12 +61,81 @@ Options:
 struct Args {
     cmd_repl: bool,
     cmd_compile: bool,
+    cmd_check: bool,
     arg_file: String,
     flag_verbose: bool,
     flag_debug: bool,
+    flag_raw: bool,
+    flag_trace_vm: bool,
+    flag_trace_compile: bool,
 }
 
 mod loggers;
+mod frontend;
+mod repl;
+mod printer;
+mod eval;
+
+use frontend::{Frontend, Registry};
+use eval::{eval_value, format_result};
+
+// Exit statuses follow the BSD sysexits(3) conventions.
+
+/// Exit status when a program could not be compiled.
+const EXIT_COMPILE: i32 = 65;
+/// Exit status when there was no input file or it could not be read.
+const EXIT_INPUT: i32 = 66;
+/// Exit status when a requested feature is not available.
+const EXIT_UNAVAILABLE: i32 = 69;
+/// Exit status when a program failed at runtime.
+const EXIT_RUNTIME: i32 = 70;
+
+/// Reads a program from the named file, or from standard input if the
+/// name is `-`. Files are only ever opened for reading.
+fn read_source(file: &str) -> Result<String, String> {
+    let mut source = String::new();
+    let result = if file == "-" {
+        io::stdin().read_to_string(&mut source)
+    } else {
+        File::open(&PathBuf::from(file))
+            .and_then(|mut f| f.read_to_string(&mut source))
+    };
+    result.map(|_| source)
+          .map_err(|error| String::from(error.description()) )
+}
+
+/// Picks the front-end for an input file based on its extension.
+/// Programs read from standard input are assumed to be Scheme.
+fn frontend_for<'a>(frontends: &'a Registry, ext_re: &Regex, file: &str)
+    -> Option<&'a Frontend> {
+    if file == "-" {
+        frontends.by_extension(".scm")
+    } else {
+        ext_re.captures(file)
+            .and_then(|c| c.name("ext"))
+            .and_then(|ext| frontends.by_extension(ext))
+    }
+}
+
+/// Reads `file` and compiles it with the front-end for its extension.
+///
+/// Errors are paired with the exit status the CLI should fail with.
+fn compile_file(frontends: &Registry, ext_re: &Regex, file: &str)
+    -> Result<List<SVMCell>, (i32, String)> {
+    let lang = try!(frontend_for(frontends, ext_re, file).ok_or(
+        (EXIT_INPUT, format!("No compiler available for {}", file))));
+    debug!("Compiling {} file {}", lang.name(), file);
+    read_source(file)
+        .map_err(   |why     | (EXIT_INPUT, why) )
+        .and_then(  |ref code| lang.compile(code)
+                                .map_err(|why| (EXIT_COMPILE, why)) )
+}
+
+/// Logs an error and exits the process with the given status.
+fn fail(status: i32, why: String) -> ! {
+    error!("{}", why);
+    process::exit(status)
+}
 
 #[allow(dead_code)]
 fn main() {
@@ -63,60 +145,44 @@ fn main() {
 
     let ext_re = Regex::new(r".+?(?P<ext>\.[^.]*$|$)").unwrap();
 
-    if args.flag_verbose {
-        let _ = log::set_logger(|max_log_level| {
-            max_log_level.set(log::LogLevelFilter::Debug);
-            Box::new(loggers::DebugLogger)
-        });
-    } else {
-        let _ = log::set_logger(|max_log_level| {
-            max_log_level.set(log::LogLevelFilter::Info);
-            Box::new(loggers::DefaultLogger)
-        });
-    };
+    let mut logger = loggers::TargetLogger::new(
+        if args.flag_verbose { log::LogLevelFilter::Debug }
+        else { log::LogLevelFilter::Info });
+    if args.flag_trace_vm {
+        logger.set_level(loggers::TARGET_SVM, log::LogLevelFilter::Trace);
+    }
+    if args.flag_trace_compile {
+        logger.set_level(loggers::TARGET_SCHEME, log::LogLevelFilter::Trace);
+    }
+    let _ = log::set_logger(|max_log_level| {
+        max_log_level.set(logger.max_level());
+        Box::new(logger)
+    });
+
+    let frontends = Registry::with_defaults();
 
     if args.cmd_repl {
-        let stdin = BufReader::new(io::stdin());
-        let mut stdout = io::stdout();
-
-        print!("scheme> ");
-        let _ = stdout.flush();
-
-        for line in stdin.lines() {
-            match line.map_err(|error| String::from(error.description()) )
-                .and_then(  |ref code| scheme::compile(code) )
-                .and_then(  |program | svm::eval_program(program, args.flag_debug) ) {
-                    Ok(result)  => println!("===> {:?}",result),
-                    Err(why)    => error!("{}", why)
-                };
-            print!("scheme> ");
-            let _ = stdout.flush();
+        repl::run(args.flag_debug, args.flag_raw);
+    } else if args.cmd_check {
+        match compile_file(&frontends, &ext_re, &args.arg_file) {
+            Ok(_)               => println!("{}: ok", args.arg_file),
+            Err((status, why))  => fail(status, why)
         }
     } else if args.cmd_compile {
-        unimplemented!()
+        // TODO: write the program to a bytecode file once seax_svm exports
+        // its bytecode encoder. Until then, only compile errors are reported.
+        match compile_file(&frontends, &ext_re, &args.arg_file) {
+            Ok(_)               => fail(EXIT_UNAVAILABLE, String::from(
+                                    "Writing bytecode is not yet supported")),
+            Err((status, why))  => fail(status, why)
+        }
     } else {
-        match ext_re
-            .captures(args.arg_file.as_ref())
-            .and_then(|c| c.name("ext")) {
-            Some(".scm")   => { // interpret scheme
-                debug!("Interpreting Scheme file {}", args.arg_file);
-                let path = PathBuf::from(args.arg_file.as_str());
-                match File::open(&path)
-                    .map_err(|error    | String::from(error.description()) )
-                    .and_then(|mut file| {
-                        let mut s = String::new();
-                        file.read_to_string(&mut s).map(|_| s)
-                            .map_err(|error| String::from(error.description()) ) })
-                    .and_then(  |ref code| scheme::compile(code) )
-                    .and_then(  |program | svm::eval_program(program, args.flag_debug) ) {
-                        Ok(result)  => println!("===> {:?}",result),
-                        Err(why)    => error!("{}", why)
-                };
-            },
-            _              => {
-                debug!("Executing binary {}", args.arg_file);
-                unimplemented!()
-            } // bin, figure out
+        match compile_file(&frontends, &ext_re, &args.arg_file)
+            .and_then(  |program | eval_value(program, args.flag_debug)
+                                    .map_err(|why| (EXIT_RUNTIME, why)) ) {
+                Ok(value)           => println!("{}",
+                                        format_result(&value, args.flag_raw)),
+                Err((status, why))  => fail(status, why)
         }
     }
 }
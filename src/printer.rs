@@ -0,0 +1,271 @@
+use svm::slist::List;
+use svm::cell::SVMCell;
+use svm::cell::SVMCell::*;
+use svm::cell::Atom;
+use svm::cell::Atom::*;
+
+/// Renders a cell as Scheme data, in the style of `write`.
+///
+/// Lists are printed as `(10 20)` rather than as chains of pairs, and
+/// characters as `#\a`. Numbers are printed in the reader's syntax, so
+/// unsigned integers get a `u` suffix and floats always have a decimal
+/// point. Since Seax Scheme compiles strings to lists of characters, any
+/// non-empty list made up entirely of characters is printed as a string
+/// literal. Booleans have no representation of their own in the SVM
+/// (`#t` is `1` and `#f` is the empty list), so they are printed as those
+/// values.
+pub fn write(cell: &SVMCell) -> String {
+    match *cell {
+        AtomCell(ref atom)  => write_atom(atom),
+        ListCell(ref list)  => write_list(list),
+        InstCell(ref inst)  => format!("{:?}", inst)
+    }
+}
+
+/// Renders every value in a list, separated by spaces.
+fn write_all(list: &List<SVMCell>) -> String {
+    list.iter()
+        .map(write)
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn write_atom(atom: &Atom) -> String {
+    match *atom {
+        UInt(value)     => format!("{}u", value),
+        SInt(value)     => format!("{}", value),
+        Float(value)    => write_float(value),
+        Char(value)     => write_char(value)
+    }
+}
+
+/// Floats always get a decimal point, so that `1.0` can't be mistaken
+/// for the integer `1` and reads back in as a float.
+///
+/// Printed numbers read back in as the same atom, with these exceptions
+/// due to the current Seax Scheme reader:
+///
+///  + negative numbers read as the name `-` (names are tried first)
+///  + floats with an integer part too large for an `isize` make the
+///    reader panic
+///  + `+nan.0`, `+inf.0` and `-inf.0` are not understood by the reader
+fn write_float(value: f64) -> String {
+    if value.is_nan() {
+        String::from("+nan.0")
+    } else if value.is_infinite() {
+        String::from(if value > 0.0 { "+inf.0" } else { "-inf.0" })
+    } else if value == 0.0 && value.is_sign_negative() {
+        // not every version of `Display` keeps the sign of negative zero
+        String::from("-0.0")
+    } else {
+        let s = format!("{}", value);
+        if s.contains('.') { s } else { s + ".0" }
+    }
+}
+
+fn write_char(c: char) -> String {
+    match c {
+        ' '         => String::from("#\\space"),
+        '\n'        => String::from("#\\newline"),
+        '\t'        => String::from("#\\tab"),
+        '\r'        => String::from("#\\return"),
+        '\u{0}'     => String::from("#\\nul"),
+        '\u{7f}'    => String::from("#\\delete"),
+        '\u{1b}'    => String::from("#\\esc"),
+        c if c.is_control() => format!("#\\x{:x}", c as u32),
+        c           => format!("#\\{}", c)
+    }
+}
+
+fn write_list(list: &List<SVMCell>) -> String {
+    let is_string = list.length() > 0 && list.iter().all(|cell|
+        match *cell { AtomCell(Char(_)) => true, _ => false });
+
+    if is_string {
+        let mut s = String::from("\"");
+        for cell in list.iter() {
+            if let AtomCell(Char(c)) = *cell {
+                match c {
+                    '"'     => s.push_str("\\\""),
+                    '\\'    => s.push_str("\\\\"),
+                    '\n'    => s.push_str("\\n"),
+                    '\t'    => s.push_str("\\t"),
+                    '\r'    => s.push_str("\\r"),
+                    c       => s.push(c)
+                }
+            }
+        }
+        s.push('"');
+        s
+    } else {
+        format!("({})", write_all(list))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write;
+
+    use svm::slist::List::{Cons,Nil};
+    use svm::cell::SVMCell::*;
+    use svm::cell::Atom::*;
+
+    #[test]
+    fn test_write_empty_list() {
+        assert_eq!(write(&ListCell(box Nil)), "()");
+    }
+
+    #[test]
+    fn test_write_flat_list() {
+        assert_eq!(
+            write(&ListCell(box list!(
+                AtomCell(SInt(10)), AtomCell(SInt(20))
+            ))),
+            "(10 20)"
+        );
+    }
+
+    #[test]
+    fn test_write_nested_lists() {
+        assert_eq!(
+            write(&ListCell(box list!(
+                AtomCell(SInt(1)),
+                ListCell(box list!(AtomCell(SInt(2)), AtomCell(SInt(3)))),
+                ListCell(box Nil)
+            ))),
+            "(1 (2 3) ())"
+        );
+    }
+
+    #[test]
+    fn test_write_char_list_as_string() {
+        assert_eq!(
+            write(&ListCell(box list!(AtomCell(Char('h')), AtomCell(Char('i'))))),
+            "\"hi\""
+        );
+    }
+
+    #[test]
+    fn test_write_mixed_list_not_string() {
+        assert_eq!(
+            write(&ListCell(box list!(AtomCell(Char('a')), AtomCell(SInt(1))))),
+            "(#\\a 1)"
+        );
+    }
+
+    #[test]
+    fn test_write_string_in_list() {
+        assert_eq!(
+            write(&ListCell(box list!(
+                AtomCell(SInt(1)),
+                ListCell(box list!(AtomCell(Char('a')), AtomCell(Char('b'))))
+            ))),
+            "(1 \"ab\")"
+        );
+    }
+
+    #[test]
+    fn test_write_string_escapes() {
+        assert_eq!(
+            write(&ListCell(box list!(
+                AtomCell(Char('"')),
+                AtomCell(Char('\\')),
+                AtomCell(Char('\n')),
+                AtomCell(Char('\t')),
+                AtomCell(Char('\r'))
+            ))),
+            "\"\\\"\\\\\\n\\t\\r\""
+        );
+    }
+
+    #[test]
+    fn test_write_chars() {
+        assert_eq!(write(&AtomCell(Char('a'))), "#\\a");
+        assert_eq!(write(&AtomCell(Char(' '))), "#\\space");
+        assert_eq!(write(&AtomCell(Char('\n'))), "#\\newline");
+        assert_eq!(write(&AtomCell(Char('\t'))), "#\\tab");
+        assert_eq!(write(&AtomCell(Char('\u{0}'))), "#\\nul");
+        assert_eq!(write(&AtomCell(Char('\u{7f}'))), "#\\delete");
+        assert_eq!(write(&AtomCell(Char('\u{1}'))), "#\\x1");
+    }
+
+    mod round_trip {
+        use super::super::write;
+
+        use svm::cell::SVMCell::AtomCell;
+        use svm::cell::Atom;
+        use svm::cell::Atom::*;
+
+        use scheme::parser::parse;
+        use scheme::ast::ExprNode::NumConst;
+        use scheme::ast::NumNode;
+        use scheme::ast::NumNode::*;
+        use scheme::ast::{IntNode, UIntNode, FloatNode};
+
+        fn read_back(atom: Atom) -> Result<NumNode, String> {
+            match parse(write(&AtomCell(atom)).as_ref()) {
+                Ok(NumConst(node))  => Ok(node),
+                Ok(other)           => Err(format!("not a number: {:?}", other)),
+                Err(why)            => Err(why)
+            }
+        }
+
+        #[test]
+        fn test_round_trip_uint() {
+            assert_eq!(read_back(UInt(0)), Ok(UIntConst(UIntNode { value: 0 })));
+            assert_eq!(read_back(UInt(42)), Ok(UIntConst(UIntNode { value: 42 })));
+        }
+
+        #[test]
+        fn test_round_trip_sint() {
+            assert_eq!(read_back(SInt(0)), Ok(IntConst(IntNode { value: 0 })));
+            assert_eq!(read_back(SInt(42)), Ok(IntConst(IntNode { value: 42 })));
+        }
+
+        /// Ignored until the reader supports negative literals (it parses
+        /// the leading `-` as an operator name).
+        #[test]
+        #[ignore]
+        fn test_round_trip_negative_sint() {
+            assert_eq!(write(&AtomCell(SInt(-7))), "-7");
+            assert_eq!(read_back(SInt(-7)), Ok(IntConst(IntNode { value: -7 })));
+        }
+
+        #[test]
+        fn test_round_trip_float() {
+            assert_eq!(write(&AtomCell(Float(1.0))), "1.0");
+            assert_eq!(read_back(Float(1.0)),
+                Ok(FloatConst(FloatNode { value: 1.0 })));
+            assert_eq!(read_back(Float(0.5)),
+                Ok(FloatConst(FloatNode { value: 0.5 })));
+        }
+
+        #[test]
+        #[cfg(target_pointer_width = "64")]
+        fn test_round_trip_large_float() {
+            assert_eq!(write(&AtomCell(Float(1e15))), "1000000000000000.0");
+            assert_eq!(read_back(Float(1e15)),
+                Ok(FloatConst(FloatNode { value: 1e15 })));
+        }
+
+        /// Ignored until the reader supports negative floats (its float
+        /// syntax has no sign).
+        #[test]
+        #[ignore]
+        fn test_round_trip_negative_float() {
+            assert_eq!(write(&AtomCell(Float(-0.0))), "-0.0");
+            assert_eq!(read_back(Float(-0.0)),
+                Ok(FloatConst(FloatNode { value: -0.0 })));
+            assert_eq!(read_back(Float(-0.5)),
+                Ok(FloatConst(FloatNode { value: -0.5 })));
+        }
+
+        #[test]
+        fn test_write_special_floats() {
+            use std::f64;
+            assert_eq!(write(&AtomCell(Float(f64::NAN))), "+nan.0");
+            assert_eq!(write(&AtomCell(Float(f64::INFINITY))), "+inf.0");
+            assert_eq!(write(&AtomCell(Float(f64::NEG_INFINITY))), "-inf.0");
+        }
+    }
+}
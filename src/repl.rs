@@ -0,0 +1,125 @@
+use scheme;
+use scheme::ast::ASTNode;
+
+use eval::{eval_value, format_result};
+
+use std::io;
+use std::io::{Write, BufRead, BufReader};
+use std::error::Error;
+
+static HELP: &'static str = "\
+REPL commands:
+    :ast <expr>       Print the abstract syntax tree for <expr>
+    :compile <expr>   Print the SVM instructions <expr> compiles to
+    :help             Show this message
+
+Anything else is evaluated as a Scheme expression.";
+
+/// Runs the Scheme read-eval-print loop until standard input is closed.
+///
+/// `debug` is passed through to the VM and `raw` to `format_result`.
+pub fn run(debug: bool, raw: bool) {
+    let stdin = BufReader::new(io::stdin());
+    let mut stdout = io::stdout();
+
+    print!("scheme> ");
+    let _ = stdout.flush();
+
+    for line in stdin.lines() {
+        match line.map_err(|error| String::from(error.description()) )
+            .and_then(  |ref line| eval_line(line, debug, raw) ) {
+                Ok(output)  => println!("{}", output),
+                Err(why)    => error!("{}", why)
+            };
+        print!("scheme> ");
+        let _ = stdout.flush();
+    }
+}
+
+/// Evaluates one line of REPL input, returning the text to print.
+///
+/// Lines starting with `:` are REPL commands; everything else is
+/// compiled and run as a Scheme expression.
+fn eval_line(line: &str, debug: bool, raw: bool) -> Result<String, String> {
+    let line = line.trim();
+    if line.starts_with(":") {
+        let (command, expr) = match line.find(|c: char| c.is_whitespace()) {
+            Some(i) => (&line[..i], line[i..].trim()),
+            None    => (line, "")
+        };
+        match command {
+            ":ast" | ":compile" if expr.is_empty()
+                        => Err(format!(
+                            "{} needs an expression, try :help", command)),
+            ":ast"      => scheme::parser::parse(expr)
+                            .map(|tree| tree.prettyprint()),
+            ":compile"  => scheme::compile(expr)
+                            .map(|program| format!("{:?}", program)),
+            ":help"     => Ok(String::from(HELP)),
+            _           => Err(format!(
+                            "Unknown command {}, try :help", command))
+        }
+    } else {
+        scheme::compile(line)
+            .and_then(  |program | eval_value(program, debug) )
+            .map(       |ref value| format_result(value, raw) )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{eval_line, HELP};
+
+    use scheme;
+    use scheme::ast::ASTNode;
+
+    #[test]
+    fn test_help() {
+        assert_eq!(eval_line(":help", false, false), Ok(String::from(HELP)));
+    }
+
+    #[test]
+    fn test_unknown_command() {
+        assert_eq!(eval_line(":foo", false, false),
+            Err(String::from("Unknown command :foo, try :help")));
+        assert_eq!(eval_line(":foo (+ 1 2)", false, false),
+            Err(String::from("Unknown command :foo, try :help")));
+    }
+
+    #[test]
+    fn test_empty_expression() {
+        assert_eq!(eval_line(":ast", false, false),
+            Err(String::from(":ast needs an expression, try :help")));
+        assert_eq!(eval_line(":compile   ", false, false),
+            Err(String::from(":compile needs an expression, try :help")));
+    }
+
+    #[test]
+    fn test_ast() {
+        assert_eq!(eval_line(":ast (+ 10 10)", false, false),
+            scheme::parser::parse("(+ 10 10)").map(|tree| tree.prettyprint()));
+    }
+
+    #[test]
+    fn test_compile() {
+        assert_eq!(eval_line(":compile (+ 10 10)", false, false),
+            Ok(String::from("(LDC . (10 . (LDC . (10 . (ADD . nil)))))")));
+    }
+
+    #[test]
+    fn test_whitespace_trimmed() {
+        assert_eq!(eval_line("  :help  ", false, false), Ok(String::from(HELP)));
+        assert_eq!(eval_line(":compile\t  (+ 10 10)  \n", false, false),
+            eval_line(":compile (+ 10 10)", false, false));
+        assert_eq!(eval_line("  (+ 10 10)  ", false, false),
+            Ok(String::from("===> 20")));
+    }
+
+    #[test]
+    fn test_eval() {
+        assert_eq!(eval_line("(+ 10 10)", false, false),
+            Ok(String::from("===> 20")));
+        assert_eq!(eval_line("(+ 10 10)", false, true),
+            Ok(String::from("===> 20")));
+    }
+}
@@ -132,3 +132,98 @@ impl_test!( test_nested_lambda,
     "((lambda (z) ((lambda (x y) (+ (- x y) z)) 3 5)) 6)",
     &AtomCell(SInt(4))
 );
+
+/// Golden-file tests.
+///
+/// Every `.scm` file in `tests/testdata` is compiled and run. The `Debug`
+/// form of the value left on top of the stack is compared against the
+/// matching `.out` file, and, if a `.svm` file is present, the `Debug` form
+/// of the compiled control list is compared against it. Trailing whitespace
+/// in the expected files is ignored.
+///
+/// If a `.err` file is present instead, the program is expected to fail to
+/// compile, with an error message containing the text of the `.err` file.
+///
+/// To add a scenario, drop a new `foo.scm` and `foo.out` (and optionally
+/// `foo.svm`), or `foo.scm` and `foo.err`, into `tests/testdata`.
+#[test]
+fn test_golden_files() {
+    use std::fs::{self, File};
+    use std::io::Read;
+    use std::path::Path;
+
+    fn read(path: &Path) -> String {
+        let mut s = String::new();
+        File::open(path)
+            .and_then(|mut f| f.read_to_string(&mut s))
+            .unwrap_or_else(|e| panic!("could not read {}: {}", path.display(), e));
+        s
+    }
+
+    let mut failures = Vec::new();
+    let mut count = 0;
+
+    for entry in fs::read_dir(Path::new("tests/testdata")).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().map_or(true, |ext| ext != "scm") {
+            continue;
+        }
+        count += 1;
+
+        let name = path.display().to_string();
+        let err_path = path.with_extension("err");
+        if err_path.exists() {
+            let expected = read(&err_path);
+            match scheme::compile(read(&path).trim()) {
+                Ok(program) => failures.push(format!(
+                    "{}: expected a compile error, but compiled to: {:?}",
+                    name, program)),
+                Err(ref why) if !why.contains(expected.trim_right()) =>
+                    failures.push(format!(
+                        "{}: compile error differs\n    expected: {}\n    actual:   {}",
+                        name, expected.trim_right(), why)),
+                Err(_) => {}
+            }
+            continue;
+        }
+
+        let program = match scheme::compile(read(&path).trim()) {
+            Ok(program) => program,
+            Err(why)    => {
+                failures.push(format!("{}: compile error: {}", name, why));
+                continue
+            }
+        };
+
+        let svm_path = path.with_extension("svm");
+        if svm_path.exists() {
+            let expected = read(&svm_path);
+            let actual   = format!("{:?}", program);
+            if actual != expected.trim_right() {
+                failures.push(format!(
+                    "{}: compiled code differs\n    expected: {}\n    actual:   {}",
+                    name, expected.trim_right(), actual));
+            }
+        }
+
+        let expected = read(&path.with_extension("out"));
+        match svm::eval_program(program, true) {
+            Ok(stack) => {
+                let actual = stack.peek()
+                                  .map_or(String::from("<empty stack>"),
+                                          |cell| format!("{:?}", cell));
+                if actual != expected.trim_right() {
+                    failures.push(format!(
+                        "{}: result differs\n    expected: {}\n    actual:   {}",
+                        name, expected.trim_right(), actual));
+                }
+            },
+            Err(why) => failures.push(format!("{}: runtime error: {}", name, why))
+        }
+    }
+
+    assert!(count > 0, "no golden files found in tests/testdata");
+    assert!(failures.is_empty(),
+        "{} of {} golden file(s) failed:\n{}",
+        failures.len(), count, failures.join("\n"));
+}
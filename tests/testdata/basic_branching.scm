@@ -0,0 +1 @@
+(+ 10 (if (nil? nil) 10 20))
@@ -0,0 +1 @@
+(cons 10 (cons 20 nil))
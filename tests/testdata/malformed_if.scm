@@ -0,0 +1 @@
+(if (nil? nil) 10)